@@ -26,6 +26,33 @@ pub enum AvifInfoError {
 // AVIF file.
 type AvifInfoResult<T> = Result<T, AvifInfoError>;
 
+// Frame/timing summary for an animated AVIF image sequence, decoded from "moov".
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SequenceInfo {
+    // Number of "trak" boxes found directly under "moov".
+    pub track_count: u32,
+    // Total sample count of the first video "trak" (the one whose "mdia"/"hdlr" handler_type is
+    // "vide"), summed from its "stbl"/"stts" sample table. None if no video "trak" could be
+    // resolved, e.g. because its sample table sits behind a structure this crate does not parse.
+    pub frame_count: Option<u32>,
+    // "mvhd" timescale, in units per second, and duration in timescale units. None if "mvhd"
+    // was not found.
+    pub timescale: Option<u32>,
+    pub duration: Option<u64>,
+}
+
+// Mastering display colour volume, as signalled by an "mdcv" property. Chromaticity coordinates
+// are in increments of 0.00002, per CTA-861.3.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MasteringDisplayColorVolume {
+    // r, g, b chromaticity coordinates, in this order.
+    pub primaries: [(u16, u16); 3],
+    pub white_point: (u16, u16),
+    // In increments of 0.0001 candelas per square metre.
+    pub max_luminance: u32,
+    pub min_luminance: u32,
+}
+
 #[derive(Debug, Default, PartialEq)]
 pub struct Features {
     // In number of pixels. Ignores crop and rotation.
@@ -53,6 +80,81 @@ pub struct Features {
 
     // Number of bytes of the primary item id.
     pub primary_item_id_bytes: u8,
+
+    // Start location and size in bytes of the primary item's coded data, i.e. its first extent
+    // resolved through "iloc", if any. Expressed relative to the beginning of the given payload,
+    // like primary_item_id_location above.
+    pub primary_item_location: Option<usize>,
+    pub primary_item_size: Option<usize>,
+
+    // Counter-clockwise rotation to apply, in degrees. One of 0, 90, 180, 270. Deduced from the
+    // "irot" property, if any.
+    pub rotation_degrees: u16,
+
+    // True if the image should be mirrored after rotation, as signalled by an "imir" property.
+    pub mirrored: bool,
+
+    // Axis of the "imir" mirroring, if any: Some(0) for a vertical axis (left-right mirroring)
+    // or Some(1) for a horizontal axis (top-bottom mirroring). None if "mirrored" is false.
+    pub mirror_axis: Option<u8>,
+
+    // Width and height in number of pixels, after applying the "clap" crop (if any) and the
+    // "irot" rotation (if any). Unlike width/height above, this is what should be displayed.
+    pub oriented_width: u32,
+    pub oriented_height: u32,
+
+    // CICP color description, deduced from an "nclx" "colr" property, if any.
+    pub color_primaries: Option<u16>,
+    pub transfer_characteristics: Option<u16>,
+    pub matrix_coefficients: Option<u16>,
+    pub full_range: Option<bool>,
+
+    // Start location and size in bytes of an embedded ICC profile, deduced from an "rICC" or
+    // "prof" "colr" property, if any. Expressed relative to the beginning of the given payload,
+    // like primary_item_id_location above.
+    pub icc_profile_location: Option<usize>,
+    pub icc_profile_size: Option<usize>,
+
+    // Start location and size in bytes of the Exif metadata payload for the primary item, if
+    // any, resolved through "iinf"/"iref"/"iloc". Expressed relative to the beginning of the
+    // given payload, like primary_item_id_location above.
+    pub exif_location: Option<usize>,
+    pub exif_size: Option<usize>,
+
+    // Same as exif_location/exif_size but for the XMP metadata payload.
+    pub xmp_location: Option<usize>,
+    pub xmp_size: Option<usize>,
+
+    // The "ftyp" major_brand, e.g. b"avif".
+    pub major_brand: [u8; 4],
+    // The "ftyp" minor_version. Informative only, per ISO/IEC 14496-12:2012(E) 4.3.1.
+    pub minor_version: u32,
+
+    // The "ftyp" compatible_brands, in file order, truncated to the first
+    // AVIFINFO_MAX_COMPATIBLE_BRANDS entries.
+    pub compatible_brands: [[u8; 4]; AVIFINFO_MAX_COMPATIBLE_BRANDS],
+    pub num_compatible_brands: usize,
+
+    // True if this is an animated AVIF image sequence (brand "avis"/"msf1", or a "moov" box was
+    // seen before "meta") rather than a single still image.
+    pub is_sequence: bool,
+    // Frame/timing summary decoded from "moov", if this is an animated AVIF image sequence and
+    // "moov" could be parsed. None for a still image, or if "moov" was present but unparsable.
+    // Parsing stops once "meta" is parsed, so this is also None when "moov" follows "meta".
+    pub sequence_info: Option<SequenceInfo>,
+    // True if the "mif1" structural brand (plain HEIF/AVIF still image container) is the major
+    // brand or one of the compatible_brands.
+    pub has_mif1_brand: bool,
+    // True if the "miaf" structural brand (MIAF conformance) is the major brand or one of the
+    // compatible_brands.
+    pub has_miaf_brand: bool,
+
+    // HDR content light level, deduced from a "clli" property, if any.
+    pub max_cll: Option<u16>,
+    pub max_pall: Option<u16>,
+
+    // HDR mastering display colour volume, deduced from an "mdcv" property, if any.
+    pub mastering_display: Option<MasteringDisplayColorVolume>,
 }
 
 //------------------------------------------------------------------------------
@@ -60,10 +162,11 @@ pub struct Features {
 // Status returned when reading the content of a box (or file).
 #[derive(PartialEq)]
 enum InternalError {
-    NotFound,  // Input correctly parsed but information is missing or elsewhere.
-    Truncated, // Input correctly parsed until missing bytes to continue.
-    Aborted,   // Input correctly parsed until stopped to avoid timeout or crash.
-    Invalid,   // Input incorrectly parsed.
+    NotFound,         // Input correctly parsed but information is missing or elsewhere.
+    Truncated(usize), // Input correctly parsed until missing bytes to continue. Carries the
+    // minimum number of additional bytes known to be needed to make progress, if any.
+    Aborted, // Input correctly parsed until stopped to avoid timeout or crash.
+    Invalid, // Input incorrectly parsed.
 }
 
 // Ok means "Input correctly parsed and information retrieved".
@@ -72,7 +175,7 @@ type InternalResult<T> = Result<T, InternalError>;
 impl From<InternalError> for AvifInfoError {
     fn from(error: InternalError) -> Self {
         match error {
-            InternalError::NotFound | InternalError::Truncated => AvifInfoError::NotEnoughData,
+            InternalError::NotFound | InternalError::Truncated(_) => AvifInfoError::NotEnoughData,
             InternalError::Aborted => AvifInfoError::TooComplex,
             InternalError::Invalid => AvifInfoError::InvalidFile,
         }
@@ -87,6 +190,14 @@ const AVIFINFO_MAX_VALUE: u8 = u8::MAX;
 const AVIFINFO_MAX_TILES: usize = 16;
 const AVIFINFO_MAX_PROPS: usize = 32;
 const AVIFINFO_MAX_FEATURES: usize = 8;
+const AVIFINFO_MAX_ITEM_LOCATIONS: usize = 16;
+const AVIFINFO_MAX_COMPATIBLE_BRANDS: usize = 16;
+// Past this many "trak" boxes directly under "moov", extra ones are only counted, not descended
+// into looking for a video track.
+const AVIFINFO_MAX_TRACKS: usize = 16;
+// Max size of an 'idat' box buffered in full, to resolve item data stored with
+// construction_method 1. A 'grid' descriptor is at most 12 bytes, so this leaves headroom.
+const AVIFINFO_MAX_IDAT_SIZE: usize = 32;
 const AVIFINFO_UNDEFINED: u8 = 0;
 
 //------------------------------------------------------------------------------
@@ -110,7 +221,8 @@ impl Stream<'_> {
         self.skip(num_bytes)?;
         match &self.data {
             Some(data) if self.offset <= data.len() => Ok(&data[offset..self.offset]),
-            _ => Err(InternalError::Truncated),
+            Some(data) => Err(InternalError::Truncated(self.offset - data.len())),
+            None => Err(InternalError::Truncated(self.offset)),
         }
     }
 
@@ -143,6 +255,17 @@ impl Stream<'_> {
         }
     }
 
+    // Same as read_uint() but for the 0/4/8-byte field widths used by "iloc" (ISO/IEC
+    // 14496-12:2015(E) 8.11.3.2), where 0 means the field is absent.
+    fn read_uint64(&mut self, num_bytes: u8) -> InternalResult<u64> {
+        match num_bytes {
+            0 => Ok(0),
+            4 => Ok(self.read_u32()? as u64),
+            8 => Ok(self.read_u64()?),
+            _ => Err(InternalError::Aborted),
+        }
+    }
+
     fn read_4cc(&mut self) -> InternalResult<&[u8; 4]> {
         Ok(self.read(4)?.try_into().unwrap())
     }
@@ -222,6 +345,68 @@ struct InternalChanProp {
     num_channels: u8,
 }
 
+#[derive(Default)]
+struct InternalRotProp {
+    property_index: u8,
+    rotation_degrees: u16,
+}
+
+#[derive(Default)]
+struct InternalMirrorProp {
+    property_index: u8,
+    axis: u8,
+}
+
+#[derive(Default)]
+struct InternalClapProp {
+    property_index: u8,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Default)]
+struct InternalClliProp {
+    property_index: u8,
+    max_cll: u16,
+    max_pall: u16,
+}
+
+#[derive(Default)]
+struct InternalMdcvProp {
+    property_index: u8,
+    mastering_display: MasteringDisplayColorVolume,
+}
+
+#[derive(Default)]
+struct InternalItemLoc {
+    item_id: u8,
+    offset: usize,
+    size: usize,
+}
+
+#[derive(Default)]
+struct InternalCdscRef {
+    // The item describing another one, e.g. an "Exif" or "mime" item.
+    metadata_item_id: u8,
+    // The item being described, e.g. the primary item.
+    described_item_id: u8,
+}
+
+#[derive(Default)]
+struct InternalColorProp {
+    property_index: u8,
+    // Set when this property is an "nclx" "colr".
+    has_nclx: bool,
+    color_primaries: u16,
+    transfer_characteristics: u16,
+    matrix_coefficients: u16,
+    full_range: bool,
+    // Set when this property is an "rICC" or "prof" "colr".
+    has_icc: bool,
+    icc_profile_location: usize,
+    icc_profile_size: usize,
+}
+
 #[derive(Default)]
 struct InternalFeatures {
     has_primary_item: bool,     // True if "pitm" was parsed.
@@ -242,6 +427,38 @@ struct InternalFeatures {
     dim_props: [InternalDimProp; AVIFINFO_MAX_FEATURES],
     num_chan_props: usize,
     chan_props: [InternalChanProp; AVIFINFO_MAX_FEATURES],
+    num_rot_props: usize,
+    rot_props: [InternalRotProp; AVIFINFO_MAX_FEATURES],
+    num_mirror_props: usize,
+    mirror_props: [InternalMirrorProp; AVIFINFO_MAX_FEATURES],
+    num_clap_props: usize,
+    clap_props: [InternalClapProp; AVIFINFO_MAX_FEATURES],
+    num_color_props: usize,
+    color_props: [InternalColorProp; AVIFINFO_MAX_FEATURES],
+    num_clli_props: usize,
+    clli_props: [InternalClliProp; AVIFINFO_MAX_FEATURES],
+    num_mdcv_props: usize,
+    mdcv_props: [InternalMdcvProp; AVIFINFO_MAX_FEATURES],
+
+    exif_item_id: u8, // Id of the "Exif" item, > 0 if present.
+    xmp_item_id: u8,  // Id of the "mime" "application/rdf+xml" item, > 0 if present.
+    num_item_locs: usize,
+    item_locs: [InternalItemLoc; AVIFINFO_MAX_ITEM_LOCATIONS],
+    num_cdsc_refs: usize,
+    cdsc_refs: [InternalCdscRef; AVIFINFO_MAX_TILES],
+
+    grid_item_id: u8, // Id of the "grid" derived image item, > 0 if present.
+    // Locations of items stored with construction_method 1, i.e. relative to 'idat' rather than
+    // to the file. The 'grid' descriptor of the primary item is read from these, and
+    // find_item_location() turns them into absolute offsets once 'idat' has been parsed.
+    num_idat_item_locs: usize,
+    idat_item_locs: [InternalItemLoc; AVIFINFO_MAX_ITEM_LOCATIONS],
+    has_idat: bool,
+    idat_size: usize,
+    idat: [u8; AVIFINFO_MAX_IDAT_SIZE],
+    // Absolute offset of the 'idat' box content, needed to turn a construction_method 1 item
+    // location (relative to 'idat') into an absolute file offset.
+    idat_stream_offset: Option<usize>,
 }
 
 impl InternalFeatures {
@@ -358,8 +575,189 @@ impl InternalFeatures {
         if self.has_alpha {
             self.primary_item_features.num_channels += 1;
         }
+        // Overrides the tile-sized dimensions above with the composited canvas size, if the
+        // primary item is a 'grid' derived image whose descriptor is available (see below).
+        self.resolve_grid_dimensions()?;
+        self.resolve_extra_props();
         Ok(())
     }
+
+    // If the primary item is a 'grid' derived image, parses its descriptor (read from the
+    // sibling 'idat' box, per construction_method 1) to recover the composited canvas size,
+    // which 'ispe' alone does not give (it is only ever set, if at all, on individual tiles).
+    // The descriptor is already fully buffered in 'idat' by the time this runs, so a short read
+    // means the file is malformed, not merely incomplete: reported as InternalError::Invalid
+    // rather than Truncated, which would otherwise surface as the misleading NotEnoughData.
+    // The 'ispe' dimensions are left as they are if the descriptor is not in an 'idat' box
+    // buffered before this runs: when it is stored with construction_method 0 (e.g. in 'mdat'),
+    // when 'idat' or 'iloc' comes after 'iprp', or when 'idat' exceeds AVIFINFO_MAX_IDAT_SIZE.
+    fn resolve_grid_dimensions(&mut self) -> InternalResult<()> {
+        if self.grid_item_id == AVIFINFO_UNDEFINED || self.grid_item_id != self.primary_item_id {
+            return Ok(());
+        }
+        if !self.has_idat {
+            return Ok(());
+        }
+        let mut item_loc = None;
+        for i in 0..self.num_idat_item_locs {
+            if self.idat_item_locs[i].item_id == self.primary_item_id {
+                item_loc = Some((self.idat_item_locs[i].offset, self.idat_item_locs[i].size));
+                break;
+            }
+        }
+        let Some((offset, size)) = item_loc else {
+            return Ok(());
+        };
+        if offset.checked_add(size).is_none_or(|end| end > self.idat_size) {
+            return Err(InternalError::Invalid);
+        }
+        let mut descriptor = [0u8; AVIFINFO_MAX_IDAT_SIZE];
+        descriptor[..size].copy_from_slice(&self.idat[offset..offset + size]);
+        let mut stream = Stream { data: Some(&descriptor[..size]), size: Some(size), offset: 0 };
+        // See the 'ImageGrid' syntax in ISO/IEC 23008-12:2017(E) 6.6.2.3.2.
+        let (width, height) = (|| -> InternalResult<(u32, u32)> {
+            stream.skip(1)?; // version. Unused.
+            let flags = stream.read_u8()?;
+            let field_size: u8 = if flags & 1 != 0 { 4 } else { 2 };
+            stream.skip(2)?; // rows_minus_one, columns_minus_one. Unused.
+            let output_width = stream.read_uint(field_size)?;
+            let output_height = stream.read_uint(field_size)?;
+            Ok((output_width, output_height))
+        })()
+        .or(Err(InternalError::Invalid))?;
+        self.primary_item_features.width = width;
+        self.primary_item_features.height = height;
+        Ok(())
+    }
+
+    // Applies the "irot"/"imir"/"clap"/"colr" properties (if any) associated with the primary
+    // item to fill in 'rotation_degrees', 'mirrored', 'oriented_width'/'oriented_height' and the
+    // color description fields. Unlike get_item_features(), this does not recurse into tiles:
+    // these properties only ever apply to the primary item itself.
+    fn resolve_extra_props(&mut self) {
+        let mut width = self.primary_item_features.width;
+        let mut height = self.primary_item_features.height;
+
+        for prop_item in 0..self.num_props {
+            if self.props[prop_item].item_id != self.primary_item_id {
+                continue;
+            }
+            let property_index = self.props[prop_item].property_index;
+
+            for i in 0..self.num_rot_props {
+                if self.rot_props[i].property_index == property_index {
+                    self.primary_item_features.rotation_degrees = self.rot_props[i].rotation_degrees;
+                    break;
+                }
+            }
+            for i in 0..self.num_mirror_props {
+                if self.mirror_props[i].property_index == property_index {
+                    self.primary_item_features.mirrored = true;
+                    self.primary_item_features.mirror_axis = Some(self.mirror_props[i].axis);
+                    break;
+                }
+            }
+            for i in 0..self.num_clap_props {
+                if self.clap_props[i].property_index == property_index {
+                    width = self.clap_props[i].width;
+                    height = self.clap_props[i].height;
+                    break;
+                }
+            }
+            for i in 0..self.num_color_props {
+                if self.color_props[i].property_index != property_index {
+                    continue;
+                }
+                let color_prop = &self.color_props[i];
+                if color_prop.has_nclx {
+                    self.primary_item_features.color_primaries = Some(color_prop.color_primaries);
+                    self.primary_item_features.transfer_characteristics =
+                        Some(color_prop.transfer_characteristics);
+                    self.primary_item_features.matrix_coefficients =
+                        Some(color_prop.matrix_coefficients);
+                    self.primary_item_features.full_range = Some(color_prop.full_range);
+                }
+                if color_prop.has_icc {
+                    self.primary_item_features.icc_profile_location =
+                        Some(color_prop.icc_profile_location);
+                    self.primary_item_features.icc_profile_size = Some(color_prop.icc_profile_size);
+                }
+                break;
+            }
+            for i in 0..self.num_clli_props {
+                if self.clli_props[i].property_index == property_index {
+                    self.primary_item_features.max_cll = Some(self.clli_props[i].max_cll);
+                    self.primary_item_features.max_pall = Some(self.clli_props[i].max_pall);
+                    break;
+                }
+            }
+            for i in 0..self.num_mdcv_props {
+                if self.mdcv_props[i].property_index == property_index {
+                    self.primary_item_features.mastering_display =
+                        Some(self.mdcv_props[i].mastering_display);
+                    break;
+                }
+            }
+        }
+
+        if matches!(self.primary_item_features.rotation_degrees, 90 | 270) {
+            std::mem::swap(&mut width, &mut height);
+        }
+        self.primary_item_features.oriented_width = width;
+        self.primary_item_features.oriented_height = height;
+
+        if self.exif_item_id != AVIFINFO_UNDEFINED
+            && self.describes_primary_item(self.exif_item_id)
+        {
+            if let Some((offset, size)) = self.find_item_location(self.exif_item_id) {
+                self.primary_item_features.exif_location = Some(offset);
+                self.primary_item_features.exif_size = Some(size);
+            }
+        }
+        if self.xmp_item_id != AVIFINFO_UNDEFINED && self.describes_primary_item(self.xmp_item_id)
+        {
+            if let Some((offset, size)) = self.find_item_location(self.xmp_item_id) {
+                self.primary_item_features.xmp_location = Some(offset);
+                self.primary_item_features.xmp_size = Some(size);
+            }
+        }
+        if let Some((offset, size)) = self.find_item_location(self.primary_item_id) {
+            self.primary_item_features.primary_item_location = Some(offset);
+            self.primary_item_features.primary_item_size = Some(size);
+        }
+    }
+
+    // True if a "cdsc" reference links 'metadata_item_id' to the primary item.
+    fn describes_primary_item(&self, metadata_item_id: u8) -> bool {
+        for i in 0..self.num_cdsc_refs {
+            if self.cdsc_refs[i].metadata_item_id == metadata_item_id
+                && self.cdsc_refs[i].described_item_id == self.primary_item_id
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Looks up the absolute file offset and length of the first extent of 'item_id' resolved
+    // through "iloc", whether stored with construction_method 0 (already an absolute file
+    // offset) or construction_method 1 (an offset into the sibling "idat" box, itself only
+    // resolvable to an absolute offset once that box has been parsed).
+    fn find_item_location(&self, item_id: u8) -> Option<(usize, usize)> {
+        for i in 0..self.num_item_locs {
+            if self.item_locs[i].item_id == item_id {
+                return Some((self.item_locs[i].offset, self.item_locs[i].size));
+            }
+        }
+        let idat_stream_offset = self.idat_stream_offset?;
+        for i in 0..self.num_idat_item_locs {
+            if self.idat_item_locs[i].item_id == item_id {
+                let offset = idat_stream_offset.checked_add(self.idat_item_locs[i].offset)?;
+                return Some((offset, self.idat_item_locs[i].size));
+            }
+        }
+        None
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -402,7 +800,19 @@ fn parse_box(
 
     let has_fullbox_header = matches!(
         &box_type,
-        b"meta" | b"pitm" | b"ipma" | b"ispe" | b"pixi" | b"iref" | b"auxC" | b"iinf" | b"infe"
+        b"meta"
+            | b"pitm"
+            | b"ipma"
+            | b"ispe"
+            | b"pixi"
+            | b"iref"
+            | b"auxC"
+            | b"iinf"
+            | b"infe"
+            | b"iloc"
+            | b"mvhd"
+            | b"hdlr"
+            | b"stts"
     );
     if has_fullbox_header {
         box_header_size += 4;
@@ -448,7 +858,13 @@ fn parse_box(
             || (&box_type == b"iref" && version <= 1)
             || (&box_type == b"auxC" && version == 0)
             || (&box_type == b"iinf" && version <= 1)
-            || (&box_type == b"infe" && version <= 2);
+            || (&box_type == b"infe" && version <= 2)
+            || (&box_type == b"iloc" && version <= 2)
+            // "mvhd"/"hdlr"/"stts" are plain ISO/IEC 14496-12 boxes, used to summarize an
+            // animated "moov" image sequence rather than being specific to the AVIF boxes above.
+            || (&box_type == b"mvhd" && version <= 1)
+            || (&box_type == b"hdlr" && version == 0)
+            || (&box_type == b"stts" && version == 0);
         // Instead of considering this file as invalid, skip unparsable boxes.
         if !is_parsable {
             box_type = *b"skip"; // FreeSpaceBox. To be ignored by readers.
@@ -467,12 +883,14 @@ impl InternalFeatures {
     fn parse_ipco(
         &mut self,
         nesting_level: u32,
+        stream_offset: usize,
         stream: &mut Stream,
         num_parsed_boxes: &mut u32,
     ) -> InternalResult<()> {
         let mut box_index = 1u8; // 1-based index. Used for iterating over properties.
         while stream.has_more_bytes() {
             let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let box_header_size = stream.num_read_bytes();
             let mut box_stream = stream.substream(box_features.content_size)?;
 
             match &box_features.box_type {
@@ -540,6 +958,113 @@ impl InternalFeatures {
                         self.data_was_skipped = true;
                     }
                 }
+                b"irot" => {
+                    // See AV1 Image File Format (AVIF) 4
+                    // at https://aomediacodec.github.io/av1-avif/#transformative-properties
+                    let data = box_stream.read_u8()?;
+                    let rotation_degrees = ((data & 0x3) as u16) * 90;
+                    if self.num_rot_props < AVIFINFO_MAX_FEATURES {
+                        self.rot_props[self.num_rot_props].property_index = box_index;
+                        self.rot_props[self.num_rot_props].rotation_degrees = rotation_degrees;
+                        self.num_rot_props += 1;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                }
+                b"imir" => {
+                    // See AV1 Image File Format (AVIF) 4
+                    // at https://aomediacodec.github.io/av1-avif/#transformative-properties
+                    // axis: 0 = vertical axis (left-right mirroring), 1 = horizontal axis
+                    // (top-bottom mirroring).
+                    let axis = box_stream.read_u8()? & 1;
+                    if self.num_mirror_props < AVIFINFO_MAX_FEATURES {
+                        self.mirror_props[self.num_mirror_props].property_index = box_index;
+                        self.mirror_props[self.num_mirror_props].axis = axis;
+                        self.num_mirror_props += 1;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                }
+                b"clap" => {
+                    // See ISO/IEC 14496-12:2015(E) 12.1.4.2
+                    let clean_aperture_width_n = box_stream.read_u32()?;
+                    let clean_aperture_width_d = box_stream.read_u32()?;
+                    let clean_aperture_height_n = box_stream.read_u32()?;
+                    let clean_aperture_height_d = box_stream.read_u32()?;
+                    box_stream.skip(16)?; // horizOff and vertOff rationals. Unused.
+                    if clean_aperture_width_d == 0 || clean_aperture_height_d == 0 {
+                        return Err(InternalError::Invalid);
+                    }
+                    if self.num_clap_props < AVIFINFO_MAX_FEATURES {
+                        self.clap_props[self.num_clap_props].property_index = box_index;
+                        self.clap_props[self.num_clap_props].width =
+                            clean_aperture_width_n / clean_aperture_width_d;
+                        self.clap_props[self.num_clap_props].height =
+                            clean_aperture_height_n / clean_aperture_height_d;
+                        self.num_clap_props += 1;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                }
+                b"colr" => {
+                    // See ISO/IEC 23008-12:2017(E) 6.5.4.2
+                    let colour_type = *box_stream.read_4cc()?;
+                    let mut color_prop = InternalColorProp { property_index: box_index, ..Default::default() };
+                    if &colour_type == b"nclx" {
+                        color_prop.has_nclx = true;
+                        color_prop.color_primaries = box_stream.read_u16()?;
+                        color_prop.transfer_characteristics = box_stream.read_u16()?;
+                        color_prop.matrix_coefficients = box_stream.read_u16()?;
+                        color_prop.full_range = (box_stream.read_u8()? & 0x80) != 0;
+                    } else if &colour_type == b"rICC" || &colour_type == b"prof" {
+                        color_prop.has_icc = true;
+                        color_prop.icc_profile_location = stream_offset
+                            .checked_add(box_header_size)
+                            .and_then(|location| location.checked_add(4))
+                            .ok_or(InternalError::Aborted)?;
+                        color_prop.icc_profile_size =
+                            box_features.content_size.unwrap_or(4).saturating_sub(4);
+                    }
+                    if color_prop.has_nclx || color_prop.has_icc {
+                        if self.num_color_props < AVIFINFO_MAX_FEATURES {
+                            self.color_props[self.num_color_props] = color_prop;
+                            self.num_color_props += 1;
+                        } else {
+                            self.data_was_skipped = true;
+                        }
+                    }
+                }
+                b"clli" => {
+                    // See ISO/IEC 23008-12:2017(E) Amd. 2, "ContentLightLevelBox"
+                    let max_cll = box_stream.read_u16()?;
+                    let max_pall = box_stream.read_u16()?;
+                    if self.num_clli_props < AVIFINFO_MAX_FEATURES {
+                        self.clli_props[self.num_clli_props].property_index = box_index;
+                        self.clli_props[self.num_clli_props].max_cll = max_cll;
+                        self.clli_props[self.num_clli_props].max_pall = max_pall;
+                        self.num_clli_props += 1;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                }
+                b"mdcv" => {
+                    // See ISO/IEC 23008-12:2017(E) Amd. 2, "MasteringDisplayColourVolumeBox"
+                    let mut mastering_display = MasteringDisplayColorVolume::default();
+                    for primary in &mut mastering_display.primaries {
+                        *primary = (box_stream.read_u16()?, box_stream.read_u16()?);
+                    }
+                    mastering_display.white_point =
+                        (box_stream.read_u16()?, box_stream.read_u16()?);
+                    mastering_display.max_luminance = box_stream.read_u32()?;
+                    mastering_display.min_luminance = box_stream.read_u32()?;
+                    if self.num_mdcv_props < AVIFINFO_MAX_FEATURES {
+                        self.mdcv_props[self.num_mdcv_props].property_index = box_index;
+                        self.mdcv_props[self.num_mdcv_props].mastering_display = mastering_display;
+                        self.num_mdcv_props += 1;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                }
                 b"auxC" => {
                     // See AV1 Image File Format (AVIF) 4
                     // at https://aomediacodec.github.io/av1-avif/#auxiliary-images
@@ -576,16 +1101,20 @@ impl InternalFeatures {
     fn parse_iprp(
         &mut self,
         nesting_level: u32,
+        stream_offset: usize,
         stream: &mut Stream,
         num_parsed_boxes: &mut u32,
     ) -> InternalResult<()> {
         while stream.has_more_bytes() {
             let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let box_header_size = stream.num_read_bytes();
             let mut box_stream = stream.substream(box_features.content_size)?;
 
             match &box_features.box_type {
                 b"ipco" => {
-                    match self.parse_ipco(nesting_level + 1, &mut box_stream, num_parsed_boxes) {
+                    let ipco_offset =
+                        stream_offset.checked_add(box_header_size).ok_or(InternalError::Aborted)?;
+                    match self.parse_ipco(nesting_level + 1, ipco_offset, &mut box_stream, num_parsed_boxes) {
                         Ok(()) => return Ok(()),
                         Err(InternalError::NotFound) => {} // Keep searching.
                         Err(error) => return Err(error),
@@ -662,36 +1191,66 @@ impl InternalFeatures {
             let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
             let mut box_stream = stream.substream(box_features.content_size)?;
 
-            if let b"dimg" = &box_features.box_type {
-                // See ISO/IEC 14496-12:2015(E) 8.11.12.2
-                let num_bytes_per_id = if box_features.version == 0 { 2 } else { 4 };
-                let from_item_id = box_stream.read_uint(num_bytes_per_id)?;
-                let reference_count = box_stream.read_u16()?;
-                for i in 0..reference_count {
-                    if i as usize >= AVIFINFO_MAX_TILES {
-                        self.data_was_skipped = true;
-                        break;
+            match &box_features.box_type {
+                b"dimg" => {
+                    // See ISO/IEC 14496-12:2015(E) 8.11.12.2
+                    let num_bytes_per_id = if box_features.version == 0 { 2 } else { 4 };
+                    let from_item_id = box_stream.read_uint(num_bytes_per_id)?;
+                    let reference_count = box_stream.read_u16()?;
+                    for i in 0..reference_count {
+                        if i as usize >= AVIFINFO_MAX_TILES {
+                            self.data_was_skipped = true;
+                            break;
+                        }
+                        let to_item_id = box_stream.read_uint(num_bytes_per_id)?;
+                        if from_item_id <= AVIFINFO_MAX_VALUE as u32
+                            && to_item_id <= AVIFINFO_MAX_VALUE as u32
+                            && self.num_tiles < AVIFINFO_MAX_TILES
+                        {
+                            self.tiles[self.num_tiles].tile_item_id = to_item_id as u8;
+                            self.tiles[self.num_tiles].parent_item_id = from_item_id as u8;
+                            self.tiles[self.num_tiles].dimg_idx = i as u8;
+                            self.num_tiles += 1;
+                        } else {
+                            self.data_was_skipped = true;
+                        }
                     }
-                    let to_item_id = box_stream.read_uint(num_bytes_per_id)?;
-                    if from_item_id <= AVIFINFO_MAX_VALUE as u32
-                        && to_item_id <= AVIFINFO_MAX_VALUE as u32
-                        && self.num_tiles < AVIFINFO_MAX_TILES
-                    {
-                        self.tiles[self.num_tiles].tile_item_id = to_item_id as u8;
-                        self.tiles[self.num_tiles].parent_item_id = from_item_id as u8;
-                        self.tiles[self.num_tiles].dimg_idx = i as u8;
-                        self.num_tiles += 1;
-                    } else {
-                        self.data_was_skipped = true;
+                }
+                b"cdsc" => {
+                    // "cdsc" ("content describes"): the referencing item describes the items it
+                    // points to, e.g. an "Exif" or "mime" metadata item describing the primary
+                    // item. See ISO/IEC 14496-12:2015(E) 8.11.12.2
+                    let num_bytes_per_id = if box_features.version == 0 { 2 } else { 4 };
+                    let metadata_item_id = box_stream.read_uint(num_bytes_per_id)?;
+                    let reference_count = box_stream.read_u16()?;
+                    for i in 0..reference_count {
+                        if i as usize >= AVIFINFO_MAX_TILES {
+                            self.data_was_skipped = true;
+                            break;
+                        }
+                        let described_item_id = box_stream.read_uint(num_bytes_per_id)?;
+                        if metadata_item_id <= AVIFINFO_MAX_VALUE as u32
+                            && described_item_id <= AVIFINFO_MAX_VALUE as u32
+                            && self.num_cdsc_refs < AVIFINFO_MAX_TILES
+                        {
+                            self.cdsc_refs[self.num_cdsc_refs].metadata_item_id =
+                                metadata_item_id as u8;
+                            self.cdsc_refs[self.num_cdsc_refs].described_item_id =
+                                described_item_id as u8;
+                            self.num_cdsc_refs += 1;
+                        } else {
+                            self.data_was_skipped = true;
+                        }
                     }
                 }
+                _ => {}
+            }
 
-                // If all features are available now, do not look further.
-                match self.get_primary_item_features() {
-                    Ok(()) => return Ok(()),
-                    Err(InternalError::NotFound) => {}
-                    Err(error) => return Err(error),
-                }
+            // If all features are available now, do not look further.
+            match self.get_primary_item_features() {
+                Ok(()) => return Ok(()),
+                Err(InternalError::NotFound) => {}
+                Err(error) => return Err(error),
             }
         }
         Err(InternalError::NotFound)
@@ -721,13 +1280,43 @@ impl InternalFeatures {
                 // Skip item_protection_index.
                 box_stream.skip(2)?;
 
-                if box_stream.read_4cc()? == b"tmap" {
+                let item_type = *box_stream.read_4cc()?;
+                if &item_type == b"tmap" {
                     // Tone Mapped Image: indicates the presence of a gain map.
                     if item_id <= AVIFINFO_MAX_VALUE as u32 {
                         self.tone_mapped_item_id = item_id as u8;
                     } else {
                         self.data_was_skipped = true;
                     }
+                } else if &item_type == b"Exif" {
+                    if item_id <= AVIFINFO_MAX_VALUE as u32 {
+                        self.exif_item_id = item_id as u8;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                } else if &item_type == b"grid" {
+                    // Derived image composed of tiles arranged in a grid. Its own item data holds
+                    // the composited canvas dimensions; only item data in 'idat' is read.
+                    if item_id <= AVIFINFO_MAX_VALUE as u32 {
+                        self.grid_item_id = item_id as u8;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                } else if &item_type == b"mime" {
+                    // item_name is a null-terminated string of unknown length. Skip it to reach
+                    // content_type, also null-terminated.
+                    while box_stream.read_u8()? != 0 {}
+                    const XMP_CONTENT_TYPE: &[u8] = b"application/rdf+xml\0";
+                    let remaining =
+                        box_stream.size.unwrap_or(0).saturating_sub(box_stream.offset);
+                    let data = box_stream.read(std::cmp::min(remaining, XMP_CONTENT_TYPE.len()))?;
+                    if data.len() == XMP_CONTENT_TYPE.len() && data == XMP_CONTENT_TYPE {
+                        if item_id <= AVIFINFO_MAX_VALUE as u32 {
+                            self.xmp_item_id = item_id as u8;
+                        } else {
+                            self.data_was_skipped = true;
+                        }
+                    }
                 }
             }
 
@@ -738,6 +1327,65 @@ impl InternalFeatures {
         Err(InternalError::NotFound)
     }
 
+    // Parses a stream of an 'iloc' box, resolving each item id to the offset and length of its
+    // first extent. construction_method 0 ("file offset") is resolved to an absolute file
+    // offset; construction_method 1 ("idat offset", only signalled with box_version 1 or 2) is
+    // kept relative to the sibling 'idat' box content, to later resolve small inline item data
+    // such as a 'grid' descriptor. construction_method 2 ("item offset") is left unresolved.
+    fn parse_iloc(&mut self, stream: &mut Stream, box_version: u8) -> InternalResult<()> {
+        // See ISO/IEC 14496-12:2015(E) 8.11.3.2
+        let sizes = stream.read_u8()?;
+        let offset_size = sizes >> 4;
+        let length_size = sizes & 0xF;
+        let other_sizes = stream.read_u8()?;
+        let base_offset_size = other_sizes >> 4;
+        let index_size = if box_version == 1 || box_version == 2 { other_sizes & 0xF } else { 0 };
+        let item_count =
+            if box_version < 2 { stream.read_u16()? as u32 } else { stream.read_u32()? };
+        for _ in 0..item_count {
+            let item_id =
+                if box_version < 2 { stream.read_u16()? as u32 } else { stream.read_u32()? };
+            let construction_method = if box_version == 1 || box_version == 2 {
+                (stream.read_u16()? & 0xF) as u8 // reserved(12 bits), construction_method(4 bits).
+            } else {
+                0
+            };
+            stream.skip(2)?; // data_reference_index.
+            let base_offset = stream.read_uint64(base_offset_size)?;
+            let extent_count = stream.read_u16()?;
+            for extent_index in 0..extent_count {
+                if index_size > 0 {
+                    stream.read_uint64(index_size)?; // extent_index. Unused.
+                }
+                let extent_offset = stream.read_uint64(offset_size)?;
+                let extent_length = stream.read_uint64(length_size)?;
+                if extent_index != 0 || construction_method > 1 {
+                    continue; // Only the first extent of a resolvable item is tracked.
+                }
+                let offset = base_offset
+                    .checked_add(extent_offset)
+                    .and_then(|value| usize::try_from(value).ok())
+                    .ok_or(InternalError::Aborted)?;
+                let size = usize::try_from(extent_length).or(Err(InternalError::Aborted))?;
+                let (num_locs, locs) = if construction_method == 0 {
+                    (&mut self.num_item_locs, &mut self.item_locs)
+                } else {
+                    (&mut self.num_idat_item_locs, &mut self.idat_item_locs)
+                };
+                if item_id <= AVIFINFO_MAX_VALUE as u32 && *num_locs < AVIFINFO_MAX_ITEM_LOCATIONS
+                {
+                    locs[*num_locs].item_id = item_id as u8;
+                    locs[*num_locs].offset = offset;
+                    locs[*num_locs].size = size;
+                    *num_locs += 1;
+                } else {
+                    self.data_was_skipped = true;
+                }
+            }
+        }
+        Err(InternalError::NotFound)
+    }
+
     // Parses a stream of a 'meta' box. It looks for the primary item ID in the
     // 'pitm' box and recurses into other boxes to find the features.
     fn parse_meta(
@@ -768,7 +1416,9 @@ impl InternalFeatures {
                     self.primary_item_features.primary_item_id_bytes = num_bytes_per_id;
                 }
                 b"iprp" => {
-                    match self.parse_iprp(nesting_level + 1, &mut box_stream, num_parsed_boxes) {
+                    let iprp_offset =
+                        stream_offset.checked_add(box_header_size).ok_or(InternalError::Aborted)?;
+                    match self.parse_iprp(nesting_level + 1, iprp_offset, &mut box_stream, num_parsed_boxes) {
                         Ok(()) => return Ok(()),
                         Err(InternalError::NotFound) => {} // Keep searching.
                         Err(error) => return Err(error),
@@ -793,6 +1443,40 @@ impl InternalFeatures {
                         Err(error) => return Err(error),
                     }
                 }
+                b"iloc" => {
+                    match self.parse_iloc(&mut box_stream, box_features.version) {
+                        Ok(()) => return Ok(()),
+                        Err(InternalError::NotFound) => {} // Keep searching.
+                        Err(error) => return Err(error),
+                    }
+                    // If all features are available now, do not look further.
+                    match self.get_primary_item_features() {
+                        Ok(()) => return Ok(()),
+                        Err(InternalError::NotFound) => {}
+                        Err(error) => return Err(error),
+                    }
+                }
+                b"idat" => {
+                    // See ISO/IEC 14496-12:2015(E) 8.11.11.2. Buffered whole (when small enough)
+                    // to later resolve item data stored with construction_method 1.
+                    let content_size = box_features.content_size.ok_or(InternalError::Invalid)?;
+                    self.idat_stream_offset =
+                        Some(stream_offset.checked_add(box_header_size).ok_or(InternalError::Aborted)?);
+                    if content_size <= AVIFINFO_MAX_IDAT_SIZE {
+                        let data = box_stream.read(content_size)?;
+                        self.idat[..content_size].copy_from_slice(data);
+                        self.idat_size = content_size;
+                        self.has_idat = true;
+                    } else {
+                        self.data_was_skipped = true;
+                    }
+                    // If all features are available now, do not look further.
+                    match self.get_primary_item_features() {
+                        Ok(()) => return Ok(()),
+                        Err(InternalError::NotFound) => {}
+                        Err(error) => return Err(error),
+                    }
+                }
                 _ => {}
             }
         }
@@ -852,12 +1536,216 @@ impl InternalFeatures {
                     &mut box_stream,
                     &mut num_parsed_boxes,
                 );
+            } else if &box_features.box_type == b"ftyp" {
+                // Consider a FileTypeBox running till the end of the file as invalid, because it
+                // should be first and a MetaBox should follow.
+                let content_size = box_features.content_size.ok_or(InternalError::Invalid)?;
+                self.parse_ftyp_box(&mut box_stream, content_size)?;
+            } else if &box_features.box_type == b"moov" {
+                // Presence of a movie box indicates an animated image sequence.
+                self.primary_item_features.is_sequence = true;
+                if box_features.content_size.is_none() {
+                    return Err(InternalError::Invalid);
+                }
+                match self.parse_moov(/* nesting_level= */ 1, &mut box_stream, &mut num_parsed_boxes) {
+                    Ok(sequence_info) => {
+                        self.primary_item_features.sequence_info = Some(sequence_info);
+                    }
+                    // A "moov" this crate cannot make sense of still signals a sequence; just
+                    // leave sequence_info unset rather than failing the whole file over it.
+                    Err(InternalError::NotFound) => {}
+                    Err(error) => return Err(error),
+                }
             } else if box_features.content_size.is_none() {
                 // This non-MetaBox runs till the end of the file. 'meta' is missing.
                 return Err(InternalError::Invalid);
             }
         }
     }
+
+    // Walks the children of a "moov" box, reading "mvhd" for timescale/duration and counting
+    // "trak" boxes, descending into the first one found to be a video track to sum its sample
+    // count. Returns InternalError::NotFound if "moov" held nothing this crate understands.
+    fn parse_moov(
+        &mut self,
+        nesting_level: u32,
+        stream: &mut Stream,
+        num_parsed_boxes: &mut u32,
+    ) -> InternalResult<SequenceInfo> {
+        let mut sequence_info = SequenceInfo::default();
+        let mut found_mvhd = false;
+        let mut found_video_track = false;
+        while stream.has_more_bytes() {
+            let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let mut box_stream = stream.substream(box_features.content_size)?;
+            match &box_features.box_type {
+                b"mvhd" => {
+                    // See ISO/IEC 14496-12:2012(E) 8.2.2.2. FullBox header already stripped.
+                    let (timescale, duration) = if box_features.version == 1 {
+                        box_stream.skip(16)?; // creation_time, modification_time (64 bits each).
+                        (box_stream.read_u32()?, box_stream.read_u64()?)
+                    } else {
+                        box_stream.skip(8)?; // creation_time, modification_time (32 bits each).
+                        (box_stream.read_u32()?, box_stream.read_u32()? as u64)
+                    };
+                    sequence_info.timescale = Some(timescale);
+                    sequence_info.duration = Some(duration);
+                    found_mvhd = true;
+                }
+                b"trak" => {
+                    sequence_info.track_count = sequence_info.track_count.saturating_add(1);
+                    if !found_video_track && sequence_info.track_count as usize > AVIFINFO_MAX_TRACKS
+                    {
+                        self.data_was_skipped = true;
+                    } else if !found_video_track {
+                        match self.parse_trak(nesting_level + 1, &mut box_stream, num_parsed_boxes)
+                        {
+                            Ok(Some(frame_count)) => {
+                                sequence_info.frame_count = Some(frame_count);
+                                found_video_track = true;
+                            }
+                            Ok(None) => {}
+                            Err(InternalError::NotFound) => {}
+                            Err(error) => return Err(error),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !found_mvhd && sequence_info.track_count == 0 {
+            return Err(InternalError::NotFound);
+        }
+        Ok(sequence_info)
+    }
+
+    // Descends into a "trak" via "mdia" to determine whether it is a video track (its "hdlr"
+    // handler_type is "vide") and, if so, its total sample count from "minf"/"stbl"/"stts".
+    // Returns None if this is not a video track or its sample table could not be located.
+    fn parse_trak(
+        &mut self,
+        nesting_level: u32,
+        stream: &mut Stream,
+        num_parsed_boxes: &mut u32,
+    ) -> InternalResult<Option<u32>> {
+        while stream.has_more_bytes() {
+            let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let mut box_stream = stream.substream(box_features.content_size)?;
+            if &box_features.box_type == b"mdia" {
+                return self.parse_mdia(nesting_level + 1, &mut box_stream, num_parsed_boxes);
+            }
+        }
+        Ok(None)
+    }
+
+    // See parse_trak() above. Looks at "hdlr" for the handler_type and at "minf" for the sample
+    // table, regardless of which comes first.
+    fn parse_mdia(
+        &mut self,
+        nesting_level: u32,
+        stream: &mut Stream,
+        num_parsed_boxes: &mut u32,
+    ) -> InternalResult<Option<u32>> {
+        let mut is_video_track = false;
+        let mut frame_count = None;
+        while stream.has_more_bytes() {
+            let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let mut box_stream = stream.substream(box_features.content_size)?;
+            match &box_features.box_type {
+                b"hdlr" => {
+                    // See ISO/IEC 14496-12:2012(E) 8.4.3.2. pre_defined(32 bits), handler_type(4cc).
+                    box_stream.skip(4)?;
+                    is_video_track = box_stream.read_4cc()? == b"vide";
+                }
+                b"minf" => {
+                    frame_count = self.parse_minf(nesting_level + 1, &mut box_stream, num_parsed_boxes)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(if is_video_track { frame_count } else { None })
+    }
+
+    // See parse_trak() above. Descends into "stbl" to find the sample table.
+    fn parse_minf(
+        &mut self,
+        nesting_level: u32,
+        stream: &mut Stream,
+        num_parsed_boxes: &mut u32,
+    ) -> InternalResult<Option<u32>> {
+        while stream.has_more_bytes() {
+            let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let mut box_stream = stream.substream(box_features.content_size)?;
+            if &box_features.box_type == b"stbl" {
+                return self.parse_stbl(nesting_level + 1, &mut box_stream, num_parsed_boxes);
+            }
+        }
+        Ok(None)
+    }
+
+    // See parse_trak() above. Sums the sample_count of every "stts" entry to get the total
+    // number of samples (frames) of the track, per ISO/IEC 14496-12:2012(E) 8.6.1.2.
+    fn parse_stbl(
+        &mut self,
+        nesting_level: u32,
+        stream: &mut Stream,
+        num_parsed_boxes: &mut u32,
+    ) -> InternalResult<Option<u32>> {
+        while stream.has_more_bytes() {
+            let box_features = parse_box(nesting_level, stream, num_parsed_boxes)?;
+            let mut box_stream = stream.substream(box_features.content_size)?;
+            if &box_features.box_type == b"stts" {
+                let entry_count = box_stream.read_u32()?;
+                let mut frame_count = 0u32;
+                for _ in 0..entry_count {
+                    let sample_count = box_stream.read_u32()?;
+                    box_stream.skip(4)?; // sample_delta. Unused.
+                    frame_count = frame_count.saturating_add(sample_count);
+                }
+                return Ok(Some(frame_count));
+            }
+        }
+        Ok(None)
+    }
+
+    // Updates the sequence/structural-brand flags for a brand found as major_brand or as one of
+    // the compatible_brands.
+    fn note_brand(&mut self, brand: &[u8; 4]) {
+        if matches!(brand, b"avis" | b"msf1") {
+            self.primary_item_features.is_sequence = true;
+        } else if brand == b"mif1" {
+            self.primary_item_features.has_mif1_brand = true;
+        } else if brand == b"miaf" {
+            self.primary_item_features.has_miaf_brand = true;
+        }
+    }
+
+    // Parses a stream of a 'ftyp' box, recording major_brand/minor_version/compatible_brands and
+    // detecting the "avis"/"msf1" brands that indicate an animated image sequence, as well as
+    // the "mif1"/"miaf" structural brands.
+    fn parse_ftyp_box(&mut self, stream: &mut Stream, content_size: usize) -> InternalResult<()> {
+        // See ISO/IEC 14496-12:2012(E) 4.3.1
+        if content_size < 8 {
+            // major_brand,minor_version
+            return Err(InternalError::Invalid);
+        }
+        let major_brand = *stream.read_4cc()?;
+        self.primary_item_features.minor_version = stream.read_u32()?;
+        self.primary_item_features.major_brand = major_brand;
+        self.note_brand(&major_brand);
+        for _ in 0..(content_size - 8) / 4 {
+            let compatible_brand = *stream.read_4cc()?;
+            self.note_brand(&compatible_brand);
+            if self.primary_item_features.num_compatible_brands < AVIFINFO_MAX_COMPATIBLE_BRANDS {
+                let i = self.primary_item_features.num_compatible_brands;
+                self.primary_item_features.compatible_brands[i] = compatible_brand;
+                self.primary_item_features.num_compatible_brands += 1;
+            } else {
+                self.data_was_skipped = true;
+            }
+        }
+        Ok(())
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -881,4 +1769,321 @@ pub fn get_features(data: &[u8]) -> AvifInfoResult<Features> {
 //------------------------------------------------------------------------------
 // Streamed input API
 
-// There is no streamed input API yet.
+// Error returned by the incremental reader-based API. Unlike AvifInfoError, NotEnoughData
+// carries a lower bound on the number of extra bytes needed to make progress, so a caller
+// reading from a slow source (network, disk) knows how much more to fetch instead of guessing.
+#[derive(Debug)]
+pub enum AvifInfoStreamError {
+    // The input was correctly parsed until now but at least bytes_needed more bytes are
+    // required to continue. The request should be repeated with a longer buffer.
+    NotEnoughData { bytes_needed: usize },
+    // The input was correctly parsed until now but it is too complex. The parsing was stopped
+    // to avoid any timeout or crash.
+    TooComplex,
+    // The input bitstream is not a valid AVIF file, truncated or not.
+    InvalidFile,
+    // Reading from the provided reader failed.
+    Io(std::io::Error),
+}
+
+impl From<InternalError> for AvifInfoStreamError {
+    fn from(error: InternalError) -> Self {
+        match error {
+            InternalError::NotFound => AvifInfoStreamError::NotEnoughData { bytes_needed: 1 },
+            InternalError::Truncated(bytes_needed) => {
+                AvifInfoStreamError::NotEnoughData { bytes_needed: bytes_needed.max(1) }
+            }
+            InternalError::Aborted => AvifInfoStreamError::TooComplex,
+            InternalError::Invalid => AvifInfoStreamError::InvalidFile,
+        }
+    }
+}
+
+// Default amount of extra data fetched ahead of the precise "bytes needed" hint, to avoid
+// issuing one tiny read per missing byte.
+const AVIFINFO_STREAM_READ_AHEAD: usize = 512;
+
+// Largest "ftyp"/"meta"/"moov" box this library is willing to buffer in memory off a streamed
+// reader. A box declaring a bigger content_size (e.g. via a crafted 64-bit "largesize") is
+// rejected as TooComplex instead of being passed to Vec::resize()/Vec::with_capacity(), which
+// would otherwise abort the process on allocation failure rather than returning an error. This
+// does not limit "mdat", which is always skipped rather than buffered.
+const AVIFINFO_MAX_STREAM_BOX_SIZE: usize = 64 * 1024 * 1024;
+
+// Reads as many features as possible out of "reader" without requiring it to be seekable, by
+// growing an in-memory buffer and retrying the parse each time more data becomes available.
+// Bytes already fetched from "reader" are never read again. Prefer get_features_from_reader()
+// below when "reader" supports Seek: it never buffers "mdat".
+pub fn get_features_from_unseekable_reader<R: std::io::Read>(
+    reader: &mut R,
+) -> Result<Features, AvifInfoStreamError> {
+    let mut buffer = Vec::new();
+    loop {
+        let mut features = InternalFeatures { ..Default::default() };
+        let parse_result =
+            features.parse_file(&mut Stream { data: Some(&buffer), size: None, offset: 0 });
+        let bytes_needed = match parse_result {
+            Ok(()) => return Ok(features.primary_item_features),
+            Err(InternalError::Truncated(bytes_needed)) => bytes_needed.max(1),
+            Err(error) => return Err(error.into()),
+        };
+        let mut probe = vec![0u8; bytes_needed.max(AVIFINFO_STREAM_READ_AHEAD)];
+        let num_read = reader.read(&mut probe).map_err(AvifInfoStreamError::Io)?;
+        if num_read == 0 {
+            return Err(AvifInfoStreamError::NotEnoughData { bytes_needed });
+        }
+        buffer.extend_from_slice(&probe[..num_read]);
+    }
+}
+
+// A source of input bytes for the streamed API below, mirroring the reference C library's
+// reader callbacks. Unlike get_features_from_unseekable_reader() above, a box whose content is
+// not needed (typically "mdat", which holds the coded pixel data and can be arbitrarily large) is
+// skipped via "skip" instead of being read into memory, so a caller backed by a file or a socket
+// never has to buffer more than one "ftyp"/"meta"/"moov" box at a time.
+pub trait AvifInfoReader {
+    // Returns the next num_bytes of input, or None if the source ran out before that many bytes
+    // became available. Implementations backed by a blocking source (a file, a socket) should
+    // block until either enough bytes were read or the source is exhausted.
+    fn read(&mut self, num_bytes: usize) -> Option<&[u8]>;
+    // Advances forward by num_bytes without returning them. Returns false if that is not
+    // possible, i.e. the source ran out before num_bytes could be skipped.
+    fn skip(&mut self, num_bytes: usize) -> bool;
+}
+
+// Reads a top-level box header (size, 4CC type) directly off "reader", handling the 64-bit
+// "largesize" extension (box_size == 1) per ISO/IEC 14496-12:2012(E) 4.2. Returns the box
+// content size and the number of header bytes that were consumed.
+fn read_stream_box_header<R: AvifInfoReader>(
+    reader: &mut R,
+) -> Result<([u8; 4], usize, usize), AvifInfoStreamError> {
+    let header = reader.read(8).ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: 8 })?;
+    let mut box_size = u32::from_be_bytes(header[0..4].try_into().unwrap()) as u64;
+    let box_type: [u8; 4] = header[4..8].try_into().unwrap();
+    let mut header_size = 8usize;
+    if box_size == 1 {
+        let large_size =
+            reader.read(8).ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: 8 })?;
+        box_size = u64::from_be_bytes(large_size.try_into().unwrap());
+        header_size = 16;
+    } else if box_size == 0 {
+        // ISO/IEC 14496-12 4.2.2: box_size==0 means this box extends to all remaining bytes.
+        // The streamed API never learns the total input length, so this cannot be supported.
+        return Err(AvifInfoStreamError::InvalidFile);
+    }
+    let content_size = usize::try_from(box_size)
+        .or(Err(AvifInfoStreamError::TooComplex))?
+        .checked_sub(header_size)
+        .ok_or(AvifInfoStreamError::InvalidFile)?;
+    Ok((box_type, content_size, header_size))
+}
+
+// Walks the top-level boxes of an AVIF file off "reader", buffering only the "ftyp", "meta" and
+// "moov" boxes (which together hold all the information this library reports) and skipping
+// everything else, in particular "mdat".
+fn parse_file_stream<R: AvifInfoReader>(
+    features: &mut InternalFeatures,
+    reader: &mut R,
+) -> Result<(), AvifInfoStreamError> {
+    let mut num_parsed_boxes = 0u32;
+    let mut num_read_bytes = 0usize;
+    loop {
+        let (box_type, content_size, header_size) = read_stream_box_header(reader)?;
+        num_read_bytes += header_size;
+        if &box_type != b"ftyp" {
+            // See parse_box() above for why top-level 'ftyp' boxes are not counted.
+            num_parsed_boxes += 1;
+            if num_parsed_boxes >= AVIFINFO_MAX_NUM_BOXES {
+                return Err(AvifInfoStreamError::TooComplex);
+            }
+        }
+        if &box_type == b"meta" {
+            // See ISO/IEC 14496-12:2015(E) 8.11.1: MetaBox is a FullBox.
+            let version = *reader
+                .read(1)
+                .ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: 1 })?
+                .first()
+                .ok_or(AvifInfoStreamError::InvalidFile)?;
+            if !reader.skip(3) {
+                return Err(AvifInfoStreamError::NotEnoughData { bytes_needed: 3 });
+            }
+            num_read_bytes += 4;
+            let meta_content_size = content_size.checked_sub(4).ok_or(AvifInfoStreamError::InvalidFile)?;
+            if meta_content_size > AVIFINFO_MAX_STREAM_BOX_SIZE {
+                return Err(AvifInfoStreamError::TooComplex);
+            }
+            if version != 0 {
+                // Instead of considering this file as invalid, skip the unparsable box.
+                if !reader.skip(meta_content_size) {
+                    return Err(AvifInfoStreamError::NotEnoughData { bytes_needed: meta_content_size });
+                }
+                num_read_bytes += meta_content_size;
+                continue;
+            }
+            let meta_content = reader
+                .read(meta_content_size)
+                .ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: meta_content_size })?;
+            let stream_offset = num_read_bytes;
+            return features
+                .parse_meta(
+                    /* nesting_level= */ 1,
+                    stream_offset,
+                    &mut Stream {
+                        data: Some(meta_content),
+                        size: Some(meta_content_size),
+                        offset: 0,
+                    },
+                    &mut num_parsed_boxes,
+                )
+                .map_err(AvifInfoStreamError::from);
+        } else if &box_type == b"ftyp" {
+            // Consider a FileTypeBox running till the end of the file as invalid, because it
+            // should be first and a MetaBox should follow.
+            if content_size > AVIFINFO_MAX_STREAM_BOX_SIZE {
+                return Err(AvifInfoStreamError::TooComplex);
+            }
+            let ftyp_content = reader
+                .read(content_size)
+                .ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: content_size })?;
+            num_read_bytes += content_size;
+            features
+                .parse_ftyp_box(
+                    &mut Stream {
+                        data: Some(ftyp_content),
+                        size: Some(content_size),
+                        offset: 0,
+                    },
+                    content_size,
+                )
+                .map_err(AvifInfoStreamError::from)?;
+        } else if &box_type == b"moov" {
+            // Presence of a movie box indicates an animated image sequence.
+            features.primary_item_features.is_sequence = true;
+            if content_size > AVIFINFO_MAX_STREAM_BOX_SIZE {
+                return Err(AvifInfoStreamError::TooComplex);
+            }
+            let moov_content = reader
+                .read(content_size)
+                .ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: content_size })?;
+            match features.parse_moov(
+                /* nesting_level= */ 1,
+                &mut Stream { data: Some(moov_content), size: Some(content_size), offset: 0 },
+                &mut num_parsed_boxes,
+            ) {
+                Ok(sequence_info) => {
+                    features.primary_item_features.sequence_info = Some(sequence_info);
+                }
+                // A "moov" this crate cannot make sense of still signals a sequence; just leave
+                // sequence_info unset rather than failing the whole file over it.
+                Err(InternalError::NotFound) => {}
+                Err(error) => return Err(error.into()),
+            }
+            num_read_bytes += content_size;
+        } else {
+            if !reader.skip(content_size) {
+                return Err(AvifInfoStreamError::NotEnoughData { bytes_needed: content_size });
+            }
+            num_read_bytes += content_size;
+        }
+    }
+}
+
+// Identifies whether "reader" looks like an AVIF file by checking its leading "ftyp" box, the
+// same way identify() does but without requiring the whole file to be buffered in memory.
+pub fn identify_stream<R: AvifInfoReader>(reader: &mut R) -> Result<(), AvifInfoStreamError> {
+    let (box_type, content_size, _header_size) = read_stream_box_header(reader)?;
+    if &box_type != b"ftyp" {
+        return Err(AvifInfoStreamError::InvalidFile);
+    }
+    if content_size > AVIFINFO_MAX_STREAM_BOX_SIZE {
+        return Err(AvifInfoStreamError::TooComplex);
+    }
+    // Same brand check as parse_ftyp(). See ISO/IEC 14496-12:2012(E) 4.3.1
+    if content_size < 8 {
+        return Err(AvifInfoStreamError::InvalidFile); // major_brand,minor_version
+    }
+    let ftyp_content = reader
+        .read(content_size)
+        .ok_or(AvifInfoStreamError::NotEnoughData { bytes_needed: content_size })?;
+    for (i, brand) in ftyp_content.chunks_exact(4).enumerate() {
+        if i == 1 {
+            continue; // Skip minor_version.
+        }
+        if brand == b"avif" || brand == b"avis" {
+            return Ok(());
+        }
+        if i > 32 {
+            return Err(AvifInfoStreamError::TooComplex); // Be reasonable.
+        }
+    }
+    Err(AvifInfoStreamError::InvalidFile)
+}
+
+// Same as get_features() but reads its input on demand from "reader" instead of requiring the
+// whole file to be buffered in memory ahead of time. See AvifInfoReader for the tradeoffs.
+pub fn get_features_stream<R: AvifInfoReader>(
+    reader: &mut R,
+) -> Result<Features, AvifInfoStreamError> {
+    let mut features = InternalFeatures { ..Default::default() };
+    parse_file_stream(&mut features, reader)?;
+    Ok(features.primary_item_features)
+}
+
+// Adapts a Read + Seek source into an AvifInfoReader: "read" pulls exactly as many bytes as
+// requested into a small rolling buffer, and "skip" becomes a forward seek, so boxes this
+// library does not care about (chiefly "mdat", the coded pixel data) are never read into memory.
+// A seek past the end of "reader" succeeds, so "skip" does not detect that the input ran out;
+// the next "read" then returns None.
+struct ReaderAdapter<'a, R: std::io::Read + std::io::Seek> {
+    reader: &'a mut R,
+    buffer: Vec<u8>,
+}
+
+impl<R: std::io::Read + std::io::Seek> AvifInfoReader for ReaderAdapter<'_, R> {
+    fn read(&mut self, num_bytes: usize) -> Option<&[u8]> {
+        // Defense in depth: parse_file_stream()/identify_stream() already reject any "ftyp",
+        // "meta" or "moov" content_size above AVIFINFO_MAX_STREAM_BOX_SIZE before calling read(),
+        // but this guard keeps ReaderAdapter itself safe against any future caller that doesn't.
+        if num_bytes > AVIFINFO_MAX_STREAM_BOX_SIZE {
+            return None;
+        }
+        self.buffer.resize(num_bytes, 0);
+        self.reader.read_exact(&mut self.buffer).ok()?;
+        Some(&self.buffer)
+    }
+
+    fn skip(&mut self, num_bytes: usize) -> bool {
+        let Ok(num_bytes) = i64::try_from(num_bytes) else {
+            return false;
+        };
+        self.reader.seek(std::io::SeekFrom::Current(num_bytes)).is_ok()
+    }
+}
+
+// AvifInfoStreamError carries a precise "bytes needed" hint and an I/O error variant that
+// AvifInfoError has no room for; both collapse into the closest AvifInfoError variant.
+fn avifinfo_error_from_stream_error(error: AvifInfoStreamError) -> AvifInfoError {
+    match error {
+        AvifInfoStreamError::NotEnoughData { .. } => AvifInfoError::NotEnoughData,
+        AvifInfoStreamError::TooComplex => AvifInfoError::TooComplex,
+        AvifInfoStreamError::InvalidFile | AvifInfoStreamError::Io(_) => AvifInfoError::InvalidFile,
+    }
+}
+
+// Identifies whether "reader" looks like an AVIF file, like identify(), but pulls bytes from
+// "reader" on demand instead of requiring the whole file to be buffered in memory ahead of time.
+pub fn identify_from_reader<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+) -> AvifInfoResult<()> {
+    let mut adapter = ReaderAdapter { reader, buffer: Vec::new() };
+    identify_stream(&mut adapter).map_err(avifinfo_error_from_stream_error)
+}
+
+// Same as get_features(), but pulls bytes from "reader" on demand instead of requiring the whole
+// file to be buffered in memory ahead of time, seeking over "mdat" rather than reading it.
+pub fn get_features_from_reader<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+) -> AvifInfoResult<Features> {
+    let mut adapter = ReaderAdapter { reader, buffer: Vec::new() };
+    get_features_stream(&mut adapter).map_err(avifinfo_error_from_stream_error)
+}
@@ -7,8 +7,9 @@
 // Media Patent License 1.0 was not distributed with this source code in the
 // PATENTS file, you can obtain it at www.aomedia.org/license/patent.
 
-use avifinfo::{get_features, identify, AvifInfoError, Features};
+use avifinfo::{get_features, get_features_from_reader, identify, AvifInfoError, Features, MasteringDisplayColorVolume, SequenceInfo};
 use std::{fs::File, io::Read};
+use std::io::Cursor;
 
 #[cfg(test)]
 fn load_file(path: &str) -> Vec<u8> {
@@ -35,6 +36,34 @@ fn single_pixel() {
             gainmap_item_id: 0,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 1,
+            oriented_height: 1,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -54,6 +83,34 @@ fn with_alpha() {
             gainmap_item_id: 0,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 2,
+            oriented_height: 2,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -73,6 +130,34 @@ fn with_gainmap() {
             gainmap_item_id: 2,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 20,
+            oriented_height: 20,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -94,6 +179,34 @@ fn set_primary_item_id_to_be_gainmap_item_id() {
             gainmap_item_id: 2,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 20,
+            oriented_height: 20,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -117,6 +230,34 @@ fn with_gainmap_tmap() {
                 gainmap_item_id: 4,
                 primary_item_id_location: 96,
                 primary_item_id_bytes: 2,
+                primary_item_location: None,
+                primary_item_size: None,
+                rotation_degrees: 0,
+                mirrored: false,
+                mirror_axis: None,
+                oriented_width: 12,
+                oriented_height: 34,
+                color_primaries: None,
+                transfer_characteristics: None,
+                matrix_coefficients: None,
+                full_range: None,
+                icc_profile_location: None,
+                icc_profile_size: None,
+                exif_location: None,
+                exif_size: None,
+                xmp_location: None,
+                xmp_size: None,
+                major_brand: *b"avif",
+                minor_version: 0,
+                compatible_brands: Default::default(),
+                num_compatible_brands: 0,
+                is_sequence: false,
+                sequence_info: None,
+                has_mif1_brand: false,
+                has_miaf_brand: false,
+                max_cll: None,
+                max_pall: None,
+                mastering_display: None,
             })
         );
     }
@@ -140,6 +281,34 @@ fn no_pixi_10b() {
             gainmap_item_id: 0,
             primary_item_id_location: 104,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 1,
+            oriented_height: 1,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -163,6 +332,34 @@ fn enough_bytes() {
             gainmap_item_id: 0,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 1,
+            oriented_height: 1,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -192,6 +389,34 @@ fn metabox_is_big() {
             gainmap_item_id: 0,
             primary_item_id_location: 104,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 1,
+            oriented_height: 1,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -218,6 +443,34 @@ fn metabox_runs_till_end_of_file() {
             gainmap_item_id: 0,
             primary_item_id_location: 96,
             primary_item_id_bytes: 2,
+            primary_item_location: None,
+            primary_item_size: None,
+            rotation_degrees: 0,
+            mirrored: false,
+            mirror_axis: None,
+            oriented_width: 1,
+            oriented_height: 1,
+            color_primaries: None,
+            transfer_characteristics: None,
+            matrix_coefficients: None,
+            full_range: None,
+            icc_profile_location: None,
+            icc_profile_size: None,
+            exif_location: None,
+            exif_size: None,
+            xmp_location: None,
+            xmp_size: None,
+            major_brand: *b"avif",
+            minor_version: 0,
+            compatible_brands: Default::default(),
+            num_compatible_brands: 0,
+            is_sequence: false,
+            sequence_info: None,
+            has_mif1_brand: false,
+            has_miaf_brand: false,
+            max_cll: None,
+            max_pall: None,
+            mastering_display: None,
         })
     );
 }
@@ -304,3 +557,554 @@ fn too_many_boxes() {
     assert_eq!(identify(input.as_slice()), Ok(()));
     assert_eq!(get_features(input.as_slice()), Err(AvifInfoError::TooComplex));
 }
+
+#[cfg(test)]
+fn bx(box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + content.len());
+    data.extend_from_slice(&((8 + content.len()) as u32).to_be_bytes());
+    data.extend_from_slice(box_type);
+    data.extend_from_slice(content);
+    data
+}
+
+#[cfg(test)]
+fn full_bx(box_type: &[u8; 4], version: u8, content: &[u8]) -> Vec<u8> {
+    let mut data = vec![version, 0, 0, 0];
+    data.extend_from_slice(content);
+    bx(box_type, &data)
+}
+
+#[cfg(test)]
+fn ftyp_box(major_brand: &[u8; 4], minor_version: u32, compatible_brands: &[[u8; 4]]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(major_brand);
+    content.extend_from_slice(&minor_version.to_be_bytes());
+    for brand in compatible_brands {
+        content.extend_from_slice(brand);
+    }
+    bx(b"ftyp", &content)
+}
+
+#[cfg(test)]
+fn ispe_box(width: u32, height: u32) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&width.to_be_bytes());
+    content.extend_from_slice(&height.to_be_bytes());
+    full_bx(b"ispe", 0, &content)
+}
+
+#[cfg(test)]
+fn pixi_box(num_channels: u8, bit_depth: u8) -> Vec<u8> {
+    let mut content = vec![num_channels];
+    content.extend(std::iter::repeat_n(bit_depth, num_channels as usize));
+    full_bx(b"pixi", 0, &content)
+}
+
+#[cfg(test)]
+fn irot_box(rotation_steps: u8) -> Vec<u8> {
+    bx(b"irot", &[rotation_steps & 0x3])
+}
+
+#[cfg(test)]
+fn clap_box(width_n: u32, width_d: u32, height_n: u32, height_d: u32) -> Vec<u8> {
+    let mut content = Vec::new();
+    for value in [width_n, width_d, height_n, height_d] {
+        content.extend_from_slice(&value.to_be_bytes());
+    }
+    content.extend_from_slice(&[0; 16]); // horizOff, vertOff. Unused.
+    bx(b"clap", &content)
+}
+
+#[cfg(test)]
+fn ipco_box(properties: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"ipco", &properties.concat())
+}
+
+#[cfg(test)]
+fn ipma_box(item_id: u16, property_indices: &[u8]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&1u32.to_be_bytes()); // entry_count.
+    content.extend_from_slice(&item_id.to_be_bytes());
+    content.push(property_indices.len() as u8);
+    content.extend_from_slice(property_indices);
+    full_bx(b"ipma", 0, &content)
+}
+
+#[cfg(test)]
+fn iprp_box(ipco: Vec<u8>, ipma: Vec<u8>) -> Vec<u8> {
+    bx(b"iprp", &[ipco, ipma].concat())
+}
+
+#[cfg(test)]
+fn pitm_box(item_id: u16) -> Vec<u8> {
+    full_bx(b"pitm", 0, &item_id.to_be_bytes())
+}
+
+#[cfg(test)]
+fn iinf_box(entries: &[Vec<u8>]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+    content.extend_from_slice(&entries.concat());
+    full_bx(b"iinf", 0, &content)
+}
+
+#[cfg(test)]
+fn meta_box(children: &[Vec<u8>]) -> Vec<u8> {
+    full_bx(b"meta", 0, &children.concat())
+}
+
+#[test]
+fn rotation_and_crop_reorient_dimensions_without_mirroring() {
+    let ipco = ipco_box(&[ispe_box(10, 20), pixi_box(3, 8), irot_box(1), clap_box(4, 1, 6, 1)]);
+    let meta = meta_box(&[pitm_box(1), iinf_box(&[]), iprp_box(ipco, ipma_box(1, &[1, 2, 3, 4]))]);
+    let file = [ftyp_box(b"avif", 0, &[]), meta].concat();
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.rotation_degrees, 90);
+    assert!(!features.mirrored);
+    // Crop to 4x6, then swap due to the 90 degree rotation.
+    assert_eq!(features.oriented_width, 6);
+    assert_eq!(features.oriented_height, 4);
+}
+
+#[cfg(test)]
+fn find(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack.windows(needle.len()).position(|window| window == needle).unwrap()
+}
+
+#[cfg(test)]
+fn colr_nclx_box(
+    color_primaries: u16,
+    transfer_characteristics: u16,
+    matrix_coefficients: u16,
+    full_range: bool,
+) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(b"nclx");
+    content.extend_from_slice(&color_primaries.to_be_bytes());
+    content.extend_from_slice(&transfer_characteristics.to_be_bytes());
+    content.extend_from_slice(&matrix_coefficients.to_be_bytes());
+    content.push(if full_range { 0x80 } else { 0 });
+    bx(b"colr", &content)
+}
+
+#[cfg(test)]
+fn colr_icc_box(icc_profile: &[u8]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(b"rICC");
+    content.extend_from_slice(icc_profile);
+    bx(b"colr", &content)
+}
+
+#[test]
+fn nclx_and_icc_colr_properties_coexist() {
+    let icc_profile = b"ICC_PROFILE_PAYLOAD_FOR_TEST".as_slice();
+    let ipco = ipco_box(&[
+        ispe_box(1, 1),
+        pixi_box(3, 8),
+        colr_nclx_box(1, 13, 6, true),
+        colr_icc_box(icc_profile),
+    ]);
+    let meta = meta_box(&[pitm_box(1), iinf_box(&[]), iprp_box(ipco, ipma_box(1, &[1, 2, 3, 4]))]);
+    let file = [ftyp_box(b"avif", 0, &[]), meta].concat();
+    let icc_profile_location = find(&file, icc_profile);
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.color_primaries, Some(1));
+    assert_eq!(features.transfer_characteristics, Some(13));
+    assert_eq!(features.matrix_coefficients, Some(6));
+    assert_eq!(features.full_range, Some(true));
+    assert_eq!(features.icc_profile_location, Some(icc_profile_location));
+    assert_eq!(features.icc_profile_size, Some(icc_profile.len()));
+}
+
+#[cfg(test)]
+fn infe_box(item_id: u16, item_type: &[u8; 4], extra: &[u8]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&item_id.to_be_bytes());
+    content.extend_from_slice(&[0, 0]); // item_protection_index. Unused.
+    content.extend_from_slice(item_type);
+    content.extend_from_slice(extra);
+    full_bx(b"infe", 2, &content) // version 2: 2-byte item_id, matching item_id above.
+}
+
+#[cfg(test)]
+fn cdsc_box(metadata_item_id: u16, described_item_id: u16) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&metadata_item_id.to_be_bytes());
+    content.extend_from_slice(&1u16.to_be_bytes()); // reference_count.
+    content.extend_from_slice(&described_item_id.to_be_bytes());
+    bx(b"cdsc", &content)
+}
+
+#[cfg(test)]
+fn iref_box(references: &[Vec<u8>]) -> Vec<u8> {
+    full_bx(b"iref", 0, &references.concat())
+}
+
+// construction_method 0 ("file offset") or 1 ("idat offset"), each with a single extent.
+#[cfg(test)]
+fn iloc_box(version: u8, entries: &[(u16, u8, u32, u32)]) -> Vec<u8> {
+    let mut content = vec![0x44, 0x00]; // offset_size=4, length_size=4; base_offset_size=index_size=0.
+    content.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+    for &(item_id, construction_method, offset, size) in entries {
+        content.extend_from_slice(&item_id.to_be_bytes());
+        if version == 1 || version == 2 {
+            content.extend_from_slice(&(construction_method as u16).to_be_bytes());
+        }
+        content.extend_from_slice(&[0, 0]); // data_reference_index. Unused.
+        content.extend_from_slice(&1u16.to_be_bytes()); // extent_count.
+        content.extend_from_slice(&offset.to_be_bytes());
+        content.extend_from_slice(&size.to_be_bytes());
+    }
+    full_bx(b"iloc", version, &content)
+}
+
+#[test]
+fn exif_and_xmp_item_locations_are_resolved_through_iinf_iref_iloc() {
+    let exif_payload = b"EXIF_SENTINEL_PAYLOAD_FOR_TEST".as_slice();
+    let xmp_payload = b"XMP_SENTINEL_PAYLOAD_FOR_TEST".as_slice();
+
+    let meta = meta_box(&[
+        pitm_box(1),
+        iinf_box(&[
+            infe_box(2, b"Exif", &[]),
+            infe_box(
+                3,
+                b"mime",
+                &[&[0u8][..], b"application/rdf+xml\0"].concat(),
+            ),
+        ]),
+        iref_box(&[cdsc_box(2, 1), cdsc_box(3, 1)]),
+        iloc_box(
+            0,
+            &[
+                (2, 0, 0 /* placeholder, overwritten below */, exif_payload.len() as u32),
+                (3, 0, 0, xmp_payload.len() as u32),
+            ],
+        ),
+        iprp_box(ipco_box(&[ispe_box(1, 1), pixi_box(3, 8)]), ipma_box(1, &[1, 2])),
+    ]);
+    let ftyp = ftyp_box(b"avif", 0, &[]);
+    let header = [ftyp.clone(), meta.clone()].concat();
+    let exif_offset = header.len() as u32;
+    let xmp_offset = exif_offset + exif_payload.len() as u32;
+    // Rebuild "iloc" now that the real offsets of the appended payloads are known.
+    let meta = meta_box(&[
+        pitm_box(1),
+        iinf_box(&[
+            infe_box(2, b"Exif", &[]),
+            infe_box(
+                3,
+                b"mime",
+                &[&[0u8][..], b"application/rdf+xml\0"].concat(),
+            ),
+        ]),
+        iref_box(&[cdsc_box(2, 1), cdsc_box(3, 1)]),
+        iloc_box(
+            0,
+            &[
+                (2, 0, exif_offset, exif_payload.len() as u32),
+                (3, 0, xmp_offset, xmp_payload.len() as u32),
+            ],
+        ),
+        iprp_box(ipco_box(&[ispe_box(1, 1), pixi_box(3, 8)]), ipma_box(1, &[1, 2])),
+    ]);
+    let mut file = [ftyp, meta].concat();
+    file.extend_from_slice(exif_payload);
+    file.extend_from_slice(xmp_payload);
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.exif_location, Some(exif_offset as usize));
+    assert_eq!(features.exif_size, Some(exif_payload.len()));
+    assert_eq!(features.xmp_location, Some(xmp_offset as usize));
+    assert_eq!(features.xmp_size, Some(xmp_payload.len()));
+}
+
+// A minimal still-image item: "ispe" + "pixi" associated to item_id through "ipma".
+#[cfg(test)]
+fn still_image_meta(item_id: u16, width: u32, height: u32) -> Vec<u8> {
+    let ipco = ipco_box(&[ispe_box(width, height), pixi_box(3, 8)]);
+    meta_box(&[pitm_box(item_id), iinf_box(&[]), iprp_box(ipco, ipma_box(item_id, &[1, 2]))])
+}
+
+#[test]
+fn ftyp_brands_report_animated_sequence_and_compatible_brands_list() {
+    let file = [
+        ftyp_box(b"avif", 0, &[*b"avis", *b"abcd", *b"efgh"]),
+        still_image_meta(1, 1, 1),
+    ]
+    .concat();
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.major_brand, *b"avif");
+    assert!(features.is_sequence);
+    assert_eq!(features.num_compatible_brands, 3);
+    assert_eq!(&features.compatible_brands[..3], &[*b"avis", *b"abcd", *b"efgh"]);
+}
+
+#[cfg(test)]
+fn clli_box(max_cll: u16, max_pall: u16) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&max_cll.to_be_bytes());
+    content.extend_from_slice(&max_pall.to_be_bytes());
+    bx(b"clli", &content)
+}
+
+#[cfg(test)]
+fn mdcv_box(
+    primaries: [(u16, u16); 3],
+    white_point: (u16, u16),
+    max_luminance: u32,
+    min_luminance: u32,
+) -> Vec<u8> {
+    let mut content = Vec::new();
+    for (x, y) in primaries {
+        content.extend_from_slice(&x.to_be_bytes());
+        content.extend_from_slice(&y.to_be_bytes());
+    }
+    content.extend_from_slice(&white_point.0.to_be_bytes());
+    content.extend_from_slice(&white_point.1.to_be_bytes());
+    content.extend_from_slice(&max_luminance.to_be_bytes());
+    content.extend_from_slice(&min_luminance.to_be_bytes());
+    bx(b"mdcv", &content)
+}
+
+#[test]
+fn clli_and_mdcv_properties_are_reported() {
+    let ipco = ipco_box(&[
+        ispe_box(1, 1),
+        pixi_box(3, 8),
+        clli_box(1000, 400),
+        mdcv_box([(1, 2), (3, 4), (5, 6)], (7, 8), 50000, 1),
+    ]);
+    let meta = meta_box(&[pitm_box(1), iinf_box(&[]), iprp_box(ipco, ipma_box(1, &[1, 2, 3, 4]))]);
+    let file = [ftyp_box(b"avif", 0, &[]), meta].concat();
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.max_cll, Some(1000));
+    assert_eq!(features.max_pall, Some(400));
+    assert_eq!(
+        features.mastering_display,
+        Some(MasteringDisplayColorVolume {
+            primaries: [(1, 2), (3, 4), (5, 6)],
+            white_point: (7, 8),
+            max_luminance: 50000,
+            min_luminance: 1,
+        })
+    );
+}
+
+#[cfg(test)]
+fn idat_box(content: &[u8]) -> Vec<u8> {
+    bx(b"idat", content)
+}
+
+// See the "ImageGrid" syntax in ISO/IEC 23008-12:2017(E) 6.6.2.3.2. flags=0 means 2-byte
+// output_width/output_height fields.
+#[cfg(test)]
+fn grid_descriptor(output_width: u16, output_height: u16) -> Vec<u8> {
+    let mut content = vec![0, 0, 0, 0]; // version, flags, rows_minus_one, columns_minus_one.
+    content.extend_from_slice(&output_width.to_be_bytes());
+    content.extend_from_slice(&output_height.to_be_bytes());
+    content
+}
+
+#[test]
+fn grid_descriptor_overrides_primary_item_dimensions() {
+    let descriptor = grid_descriptor(100, 200);
+    let meta = meta_box(&[
+        pitm_box(1),
+        iinf_box(&[infe_box(1, b"grid", &[])]),
+        iloc_box(1, &[(1, 1, 0, descriptor.len() as u32)]), // construction_method 1: idat-relative.
+        idat_box(&descriptor),
+        iprp_box(ipco_box(&[ispe_box(5, 5), pixi_box(3, 8)]), ipma_box(1, &[1, 2])),
+    ]);
+    let file = [ftyp_box(b"avif", 0, &[]), meta].concat();
+    let idat_content_offset = find(&file, &descriptor);
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.width, 100);
+    assert_eq!(features.height, 200);
+    assert_eq!(features.oriented_width, 100);
+    assert_eq!(features.oriented_height, 200);
+    assert_eq!(features.primary_item_location, Some(idat_content_offset));
+    assert_eq!(features.primary_item_size, Some(descriptor.len()));
+}
+
+#[test]
+fn minor_version_and_structural_brands_are_reported() {
+    let file = [
+        ftyp_box(b"mif1", 42, &[*b"miaf", *b"avif"]),
+        still_image_meta(1, 1, 1),
+    ]
+    .concat();
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.major_brand, *b"mif1");
+    assert_eq!(features.minor_version, 42);
+    assert!(features.has_mif1_brand);
+    assert!(features.has_miaf_brand);
+    assert!(!features.is_sequence);
+}
+
+#[test]
+fn primary_items_coded_data_extent_is_resolved_through_iloc() {
+    let payload = b"CODED_DATA_PAYLOAD_FOR_TEST".as_slice();
+    let ftyp = ftyp_box(b"avif", 0, &[]);
+    // Build the "meta" box first to know where the appended payload will land.
+    let meta_without_iloc_offset_len = {
+        let meta = meta_box(&[
+            pitm_box(1),
+            iinf_box(&[]),
+            iloc_box(0, &[(1, 0, 0, payload.len() as u32)]),
+            iprp_box(ipco_box(&[ispe_box(1, 1), pixi_box(3, 8)]), ipma_box(1, &[1, 2])),
+        ]);
+        meta.len()
+    };
+    let offset = (ftyp.len() + meta_without_iloc_offset_len) as u32;
+    let meta = meta_box(&[
+        pitm_box(1),
+        iinf_box(&[]),
+        iloc_box(0, &[(1, 0, offset, payload.len() as u32)]),
+        iprp_box(ipco_box(&[ispe_box(1, 1), pixi_box(3, 8)]), ipma_box(1, &[1, 2])),
+    ]);
+    let mut file = [ftyp, meta].concat();
+    assert_eq!(file.len() as u32, offset);
+    file.extend_from_slice(payload);
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.primary_item_location, Some(offset as usize));
+    assert_eq!(features.primary_item_size, Some(payload.len()));
+    assert_eq!(&file[offset as usize..offset as usize + payload.len()], payload);
+}
+
+#[test]
+fn streamed_api_rejects_oversized_largesize_ftyp_instead_of_aborting() {
+    // A 16-byte "ftyp" header whose box_size is the 64-bit "largesize" extension
+    // (box_size field == 1) set to u64::MAX. Before the fix, the resulting content_size would be
+    // handed straight to a buffering read, aborting the process with a capacity overflow instead
+    // of returning a Result.
+    let file: Vec<u8> = [
+        0u32.to_be_bytes().as_slice(),
+        &[0, 0, 0, 1], // box_size == 1: read the following 64-bit largesize instead.
+        b"ftyp",
+        &u64::MAX.to_be_bytes(),
+    ]
+    .concat();
+    // The leading placeholder u32 above is not part of the box; drop it.
+    let file = file[4..].to_vec();
+
+    assert_eq!(
+        get_features_from_reader(&mut Cursor::new(file)),
+        Err(AvifInfoError::TooComplex)
+    );
+}
+
+#[cfg(test)]
+fn imir_box(axis: u8) -> Vec<u8> {
+    bx(b"imir", &[axis & 1])
+}
+
+#[test]
+fn mirror_axis_distinguishes_vertical_from_horizontal_mirroring_and_combines_with_rotation_and_crop(
+) {
+    // rotation(90 degrees) + crop(3x4) + mirror(horizontal axis), associated to item 1 in that
+    // property order.
+    let ipco = ipco_box(&[
+        ispe_box(10, 20),
+        pixi_box(3, 8),
+        irot_box(1),
+        clap_box(3, 1, 4, 1),
+        imir_box(1),
+    ]);
+    let meta = meta_box(&[
+        pitm_box(1),
+        iinf_box(&[]),
+        iprp_box(ipco, ipma_box(1, &[1, 2, 3, 4, 5])),
+    ]);
+    let file = [ftyp_box(b"avif", 0, &[]), meta].concat();
+
+    let features = get_features(file.as_slice()).unwrap();
+    assert_eq!(features.rotation_degrees, 90);
+    assert!(features.mirrored);
+    assert_eq!(features.mirror_axis, Some(1));
+    assert_eq!(features.width, 10);
+    assert_eq!(features.height, 20);
+    // Crop to 3x4, then swap due to the 90 degree rotation.
+    assert_eq!(features.oriented_width, 4);
+    assert_eq!(features.oriented_height, 3);
+}
+
+#[cfg(test)]
+fn mvhd_box(timescale: u32, duration: u32) -> Vec<u8> {
+    let mut content = vec![0; 8]; // creation_time, modification_time. Unused.
+    content.extend_from_slice(&timescale.to_be_bytes());
+    content.extend_from_slice(&duration.to_be_bytes());
+    full_bx(b"mvhd", 0, &content)
+}
+
+#[cfg(test)]
+fn hdlr_box(handler_type: &[u8; 4]) -> Vec<u8> {
+    let mut content = vec![0; 4]; // pre_defined. Unused.
+    content.extend_from_slice(handler_type);
+    full_bx(b"hdlr", 0, &content)
+}
+
+#[cfg(test)]
+fn stts_box(entries: &[(u32, u32)]) -> Vec<u8> {
+    let mut content = Vec::new();
+    content.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    for &(sample_count, sample_delta) in entries {
+        content.extend_from_slice(&sample_count.to_be_bytes());
+        content.extend_from_slice(&sample_delta.to_be_bytes());
+    }
+    full_bx(b"stts", 0, &content)
+}
+
+#[cfg(test)]
+fn stbl_box(children: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"stbl", &children.concat())
+}
+
+#[cfg(test)]
+fn minf_box(children: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"minf", &children.concat())
+}
+
+#[cfg(test)]
+fn mdia_box(children: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"mdia", &children.concat())
+}
+
+#[cfg(test)]
+fn trak_box(children: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"trak", &children.concat())
+}
+
+#[cfg(test)]
+fn moov_box(children: &[Vec<u8>]) -> Vec<u8> {
+    bx(b"moov", &children.concat())
+}
+
+#[test]
+fn streamed_api_resolves_sequence_info_from_moov_same_as_slice_api() {
+    let moov = moov_box(&[
+        mvhd_box(600, 1200),
+        trak_box(&[mdia_box(&[
+            hdlr_box(b"vide"),
+            minf_box(&[stbl_box(&[stts_box(&[(5, 100)])])]),
+        ])]),
+    ]);
+    let file = [ftyp_box(b"avif", 0, &[]), moov, still_image_meta(1, 1, 1)].concat();
+
+    let slice_features = get_features(file.as_slice()).unwrap();
+    let stream_features = get_features_from_reader(&mut Cursor::new(file.clone())).unwrap();
+
+    assert_eq!(slice_features, stream_features);
+    assert!(slice_features.is_sequence);
+    assert_eq!(
+        slice_features.sequence_info,
+        Some(SequenceInfo { track_count: 1, frame_count: Some(5), timescale: Some(600), duration: Some(1200) })
+    );
+}